@@ -0,0 +1,229 @@
+use crate::frame::{Frame, OpCode};
+use crate::message::{CloseReason, DecodeError, Message};
+
+#[derive(Debug)]
+pub enum AssembleError {
+    UnexpectedContinuation,
+    UnfinishedMessage,
+    FragmentedControlFrame,
+    InvalidUtf8,
+    MalformedClose,
+    InvalidCloseCode,
+    Overflow,
+}
+
+impl From<DecodeError> for AssembleError {
+    fn from(err: DecodeError) -> Self {
+        match err {
+            DecodeError::Malformed => AssembleError::MalformedClose,
+            DecodeError::InvalidCloseCode => AssembleError::InvalidCloseCode,
+        }
+    }
+}
+
+/// Buffers fragmented Text/Binary frames into complete `Message`s, per RFC 6455 section 5.4.
+///
+/// Control frames (Close/Ping/Pong) are never fragmented and are emitted as soon as they
+/// arrive, even in the middle of an in-progress data message.
+pub struct MessageAssembler {
+    max_message_size: usize,
+    in_progress: Option<(OpCode, Vec<u8>)>,
+}
+
+impl MessageAssembler {
+    pub fn new(max_message_size: usize) -> Self {
+        MessageAssembler {
+            max_message_size,
+            in_progress: None,
+        }
+    }
+
+    /// Feeds one parsed frame in, returning a complete `Message` once enough fragments have
+    /// arrived (or immediately, for control frames and unfragmented data frames).
+    pub fn process(&mut self, frame: Frame) -> Result<Option<Message>, AssembleError> {
+        if frame.op_code.as_u8() >= 0x8 {
+            if !frame.is_last_frag {
+                return Err(AssembleError::FragmentedControlFrame);
+            }
+            return Self::control_message(frame.op_code, frame.payload).map(Some);
+        }
+        match frame.op_code {
+            OpCode::Continuation => {
+                let (_, buf) = self
+                    .in_progress
+                    .as_mut()
+                    .ok_or(AssembleError::UnexpectedContinuation)?;
+                if buf.len() + frame.payload.len() > self.max_message_size {
+                    self.in_progress = None;
+                    return Err(AssembleError::Overflow);
+                }
+                buf.extend_from_slice(&frame.payload);
+                if !frame.is_last_frag {
+                    return Ok(None);
+                }
+                let (op_code, payload) = self.in_progress.take().unwrap();
+                Self::data_message(op_code, payload).map(Some)
+            }
+            OpCode::Text | OpCode::Binary => {
+                if self.in_progress.is_some() {
+                    return Err(AssembleError::UnfinishedMessage);
+                }
+                if frame.payload.len() > self.max_message_size {
+                    return Err(AssembleError::Overflow);
+                }
+                if frame.is_last_frag {
+                    Self::data_message(frame.op_code, frame.payload).map(Some)
+                } else {
+                    self.in_progress = Some((frame.op_code, frame.payload));
+                    Ok(None)
+                }
+            }
+            OpCode::Close | OpCode::Ping | OpCode::Pong => unreachable!("handled above"),
+        }
+    }
+
+    fn data_message(op_code: OpCode, payload: Vec<u8>) -> Result<Message, AssembleError> {
+        match op_code {
+            OpCode::Text => {
+                String::from_utf8(payload)
+                    .map(Message::Text)
+                    .map_err(|_| AssembleError::InvalidUtf8)
+            }
+            OpCode::Binary => Ok(Message::Binary(payload)),
+            _ => unreachable!("only Text/Binary accumulate fragments"),
+        }
+    }
+
+    fn control_message(op_code: OpCode, payload: Vec<u8>) -> Result<Message, AssembleError> {
+        match op_code {
+            OpCode::Close => Ok(Message::Close(CloseReason::decode(&payload)?)),
+            OpCode::Ping => Ok(Message::Ping(payload)),
+            OpCode::Pong => Ok(Message::Pong(payload)),
+            _ => unreachable!("only control opcodes reach here"),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn frame(op_code: OpCode, is_last_frag: bool, payload: &[u8]) -> Frame {
+        Frame {
+            is_last_frag,
+            op_code,
+            payload: payload.to_vec(),
+        }
+    }
+
+    #[test]
+    fn reassembles_a_fragmented_text_message() {
+        let mut assembler = MessageAssembler::new(1024);
+        assert!(matches!(
+            assembler.process(frame(OpCode::Text, false, b"hello, ")),
+            Ok(None)
+        ));
+        let message = assembler
+            .process(frame(OpCode::Continuation, true, b"world!"))
+            .unwrap()
+            .unwrap();
+        assert!(matches!(message, Message::Text(text) if text == "hello, world!"));
+    }
+
+    #[test]
+    fn unfragmented_data_frame_yields_immediately() {
+        let mut assembler = MessageAssembler::new(1024);
+        let message = assembler
+            .process(frame(OpCode::Binary, true, b"abc"))
+            .unwrap()
+            .unwrap();
+        assert!(matches!(message, Message::Binary(payload) if payload == b"abc"));
+    }
+
+    #[test]
+    fn control_frames_interleave_with_an_in_progress_message() {
+        let mut assembler = MessageAssembler::new(1024);
+        assert!(matches!(
+            assembler.process(frame(OpCode::Text, false, b"hello, ")),
+            Ok(None)
+        ));
+        let ping = assembler
+            .process(frame(OpCode::Ping, true, b"ping"))
+            .unwrap()
+            .unwrap();
+        assert!(matches!(ping, Message::Ping(payload) if payload == b"ping"));
+        // The interrupted message is still in progress after the control frame.
+        let message = assembler
+            .process(frame(OpCode::Continuation, true, b"world!"))
+            .unwrap()
+            .unwrap();
+        assert!(matches!(message, Message::Text(text) if text == "hello, world!"));
+    }
+
+    #[test]
+    fn continuation_without_a_started_message_is_rejected() {
+        let mut assembler = MessageAssembler::new(1024);
+        let err = assembler
+            .process(frame(OpCode::Continuation, true, b"oops"))
+            .unwrap_err();
+        assert!(matches!(err, AssembleError::UnexpectedContinuation));
+    }
+
+    #[test]
+    fn starting_a_message_while_one_is_in_progress_is_rejected() {
+        let mut assembler = MessageAssembler::new(1024);
+        assert!(matches!(
+            assembler.process(frame(OpCode::Text, false, b"hello")),
+            Ok(None)
+        ));
+        let err = assembler
+            .process(frame(OpCode::Binary, true, b"oops"))
+            .unwrap_err();
+        assert!(matches!(err, AssembleError::UnfinishedMessage));
+    }
+
+    #[test]
+    fn fragmented_control_frames_are_rejected() {
+        let mut assembler = MessageAssembler::new(1024);
+        let err = assembler
+            .process(frame(OpCode::Ping, false, b"ping"))
+            .unwrap_err();
+        assert!(matches!(err, AssembleError::FragmentedControlFrame));
+    }
+
+    #[test]
+    fn oversized_unfragmented_message_is_rejected() {
+        let mut assembler = MessageAssembler::new(4);
+        let err = assembler
+            .process(frame(OpCode::Binary, true, b"too long"))
+            .unwrap_err();
+        assert!(matches!(err, AssembleError::Overflow));
+    }
+
+    #[test]
+    fn oversized_fragmented_message_is_rejected_and_abandoned() {
+        let mut assembler = MessageAssembler::new(4);
+        assert!(matches!(
+            assembler.process(frame(OpCode::Text, false, b"ab")),
+            Ok(None)
+        ));
+        let err = assembler
+            .process(frame(OpCode::Continuation, true, b"cde"))
+            .unwrap_err();
+        assert!(matches!(err, AssembleError::Overflow));
+        // The abandoned message doesn't leave a stale continuation behind.
+        let err = assembler
+            .process(frame(OpCode::Continuation, true, b"f"))
+            .unwrap_err();
+        assert!(matches!(err, AssembleError::UnexpectedContinuation));
+    }
+
+    #[test]
+    fn invalid_utf8_text_is_rejected() {
+        let mut assembler = MessageAssembler::new(1024);
+        let err = assembler
+            .process(frame(OpCode::Text, true, &[0xFF, 0xFE]))
+            .unwrap_err();
+        assert!(matches!(err, AssembleError::InvalidUtf8));
+    }
+}
@@ -0,0 +1,50 @@
+const DEFAULT_MAX_SIZE: usize = 64 * 1024;
+
+/// Size limits enforced while parsing and reassembling frames, to bound memory use against a
+/// malicious or buggy peer. Defaults match the `actix-web-actors` ws codec (64 KiB).
+#[derive(Debug, Clone, Copy)]
+pub struct WebSocketConfig {
+    pub(crate) max_frame_size: usize,
+    pub(crate) max_message_size: usize,
+}
+
+impl Default for WebSocketConfig {
+    fn default() -> Self {
+        WebSocketConfig {
+            max_frame_size: DEFAULT_MAX_SIZE,
+            max_message_size: DEFAULT_MAX_SIZE,
+        }
+    }
+}
+
+impl WebSocketConfig {
+    pub fn builder() -> WebSocketConfigBuilder {
+        WebSocketConfigBuilder {
+            config: WebSocketConfig::default(),
+        }
+    }
+}
+
+pub struct WebSocketConfigBuilder {
+    config: WebSocketConfig,
+}
+
+impl WebSocketConfigBuilder {
+    /// Largest single frame payload to accept, checked against the announced length before the
+    /// payload `Vec` is allocated.
+    pub fn max_frame_size(mut self, max_frame_size: usize) -> Self {
+        self.config.max_frame_size = max_frame_size;
+        self
+    }
+
+    /// Largest cumulative size of a fragmented message's payload across all its Continuation
+    /// frames.
+    pub fn max_message_size(mut self, max_message_size: usize) -> Self {
+        self.config.max_message_size = max_message_size;
+        self
+    }
+
+    pub fn build(self) -> WebSocketConfig {
+        self.config
+    }
+}
@@ -0,0 +1,214 @@
+#[derive(Debug)]
+pub enum ParseError {
+    Unfinished,
+    ReservedOpCode,
+    ReservedBit,
+    UnmaskedFrame,
+    MaskedFrame,
+    Overflow,
+}
+
+#[derive(Debug)]
+pub enum OpCode {
+    Continuation,
+    Text,
+    Binary,
+    Close,
+    Ping,
+    Pong,
+}
+
+impl OpCode {
+    pub(crate) fn as_u8(&self) -> u8 {
+        match self {
+            OpCode::Continuation => 0x0,
+            OpCode::Text => 0x1,
+            OpCode::Binary => 0x2,
+            OpCode::Close => 0x8,
+            OpCode::Ping => 0x9,
+            OpCode::Pong => 0xA,
+        }
+    }
+}
+
+#[derive(Debug)]
+pub struct Frame {
+    pub(crate) is_last_frag: bool,
+    pub(crate) op_code: OpCode,
+    pub(crate) payload: Vec<u8>,
+}
+
+/// XORs `payload` in place with the repeating 4-byte `key`, per RFC 6455 section 5.3.
+fn apply_mask(payload: &mut [u8], key: [u8; 4]) {
+    for (i, byte) in payload.iter_mut().enumerate() {
+        *byte ^= key[i % 4];
+    }
+}
+
+impl Frame {
+    /// Serializes this frame to the wire format, masking the payload with `mask` when given.
+    ///
+    /// Servers should pass `None` (masking is forbidden on server-to-client frames); clients
+    /// must always pass `Some`.
+    pub fn encode(&self, mask: Option<u32>) -> Vec<u8> {
+        let mut out = vec![(self.is_last_frag as u8) << 7 | self.op_code.as_u8()];
+        let mask_bit = if mask.is_some() { 0b10000000 } else { 0 };
+        let len = self.payload.len();
+        if len <= 125 {
+            out.push(mask_bit | len as u8);
+        } else if len <= u16::MAX as usize {
+            out.push(mask_bit | 126);
+            out.extend_from_slice(&(len as u16).to_be_bytes());
+        } else {
+            out.push(mask_bit | 127);
+            out.extend_from_slice(&(len as u64).to_be_bytes());
+        }
+        let mut payload = self.payload.clone();
+        if let Some(mask) = mask {
+            let key = mask.to_be_bytes();
+            out.extend_from_slice(&key);
+            apply_mask(&mut payload, key);
+        }
+        out.extend_from_slice(&payload);
+        out
+    }
+}
+
+pub fn parse_frame(
+    bytes: &[u8],
+    server: bool,
+    max_size: usize,
+) -> Result<(usize, Frame), ParseError> {
+    let byte0 = bytes.first().ok_or(ParseError::Unfinished)?;
+    let byte1 = bytes.get(1).ok_or(ParseError::Unfinished)?;
+    let fin = byte0 & 0b10000000 != 0;
+    if byte0 & 0b01110000 != 0 {
+        return Err(ParseError::ReservedBit);
+    }
+    let op = byte0 & 0b00001111;
+    let op = match op {
+        0x0 => OpCode::Continuation,
+        0x1 => OpCode::Text,
+        0x2 => OpCode::Binary,
+        0x8 => OpCode::Close,
+        0x9 => OpCode::Ping,
+        0xA => OpCode::Pong,
+        _ => return Err(ParseError::ReservedOpCode),
+    };
+    let is_masked = byte1 & 0b10000000 != 0;
+    let len = byte1 & 0b01111111;
+    let payload_len = match len {
+        126 => {
+            let high = *bytes.get(2).ok_or(ParseError::Unfinished)?;
+            let low = *bytes.get(3).ok_or(ParseError::Unfinished)?;
+            (high as u64) << 8 | low as u64
+        }
+        127 => {
+            if bytes.len() < 10 {
+                return Err(ParseError::Unfinished);
+            }
+            let mut result = [0; 8];
+            result.copy_from_slice(&bytes[2..10]);
+            u64::from_be_bytes(result)
+        }
+        _ => len as u64,
+    };
+    if payload_len as usize > max_size {
+        return Err(ParseError::Overflow);
+    }
+    let mask_offset = 2 + match len {
+        126 => 2,
+        127 => 8,
+        _ => 0,
+    };
+    let payload_offset = mask_offset + if is_masked { 4 } else { 0 };
+    let key = if is_masked {
+        if bytes.len() < mask_offset + 4 {
+            return Err(ParseError::Unfinished);
+        }
+        let mut result = [0; 4];
+        result.copy_from_slice(&bytes[mask_offset..mask_offset + 4]);
+        Some(result)
+    } else {
+        None
+    };
+    if server && key.is_none() {
+        return Err(ParseError::UnmaskedFrame);
+    }
+    if !server && key.is_some() {
+        return Err(ParseError::MaskedFrame);
+    }
+    let frame_end = payload_offset + payload_len as usize;
+    if bytes.len() < frame_end {
+        return Err(ParseError::Unfinished);
+    }
+    let mut payload = bytes[payload_offset..frame_end].to_vec();
+    if let Some(key) = key {
+        apply_mask(&mut payload, key);
+    }
+    Ok((
+        frame_end,
+        Frame {
+            is_last_frag: fin,
+            op_code: op,
+            payload,
+        },
+    ))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn unmasked_frame(payload: Vec<u8>) -> Frame {
+        Frame {
+            is_last_frag: true,
+            op_code: OpCode::Binary,
+            payload,
+        }
+    }
+
+    #[test]
+    fn apply_mask_is_its_own_inverse() {
+        let key = [0x12, 0x34, 0x56, 0x78];
+        let original = vec![1, 2, 3, 4, 5, 6, 7, 8, 9];
+        let mut data = original.clone();
+        apply_mask(&mut data, key);
+        assert_ne!(data, original);
+        apply_mask(&mut data, key);
+        assert_eq!(data, original);
+    }
+
+    #[test]
+    fn encode_parse_round_trip_across_length_forms() {
+        // Exercises the 7-bit, 126+u16, and 127+u64 length forms and their boundaries.
+        for &len in &[0, 125, 126, 65535, 65536] {
+            let payload = vec![0xAB; len];
+            let encoded = unmasked_frame(payload.clone()).encode(Some(0xDEADBEEF));
+            let (consumed, parsed) = parse_frame(&encoded, true, len.max(1)).unwrap();
+            assert_eq!(consumed, encoded.len(), "length form for payload len {len}");
+            assert_eq!(parsed.payload, payload, "payload round-trip for len {len}");
+        }
+    }
+
+    #[test]
+    fn parse_frame_rejects_frame_over_max_size() {
+        let encoded = unmasked_frame(vec![0; 200]).encode(Some(1));
+        let err = parse_frame(&encoded, true, 100).unwrap_err();
+        assert!(matches!(err, ParseError::Overflow));
+    }
+
+    #[test]
+    fn parse_frame_enforces_server_masking_rule() {
+        let masked = unmasked_frame(vec![1, 2, 3]).encode(Some(1));
+        let unmasked = unmasked_frame(vec![1, 2, 3]).encode(None);
+        assert!(matches!(
+            parse_frame(&unmasked, true, 64 * 1024),
+            Err(ParseError::UnmaskedFrame)
+        ));
+        assert!(matches!(
+            parse_frame(&masked, false, 64 * 1024),
+            Err(ParseError::MaskedFrame)
+        ));
+    }
+}
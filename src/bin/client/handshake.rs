@@ -0,0 +1,118 @@
+use async_std::io::{ReadExt, WriteExt};
+use async_std::net::TcpStream;
+use base64::Engine;
+use sha1::{Digest, Sha1};
+
+const ACCEPT_GUID: &str = "258EAFA5-E914-47DA-95CA-C5AB0DC85B11";
+
+/// Largest opening handshake request we'll buffer before giving up, to bound memory use against
+/// a peer that never sends the terminating "\r\n\r\n".
+const MAX_HEADER_SIZE: usize = 8 * 1024;
+
+#[derive(Debug)]
+pub enum HandshakeError {
+    Io(std::io::Error),
+    ConnectionClosed,
+    InvalidRequest,
+    MissingHeader(&'static str),
+    HeaderTooLarge,
+}
+
+impl From<std::io::Error> for HandshakeError {
+    fn from(err: std::io::Error) -> Self {
+        HandshakeError::Io(err)
+    }
+}
+
+impl std::fmt::Display for HandshakeError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            HandshakeError::Io(err) => write!(f, "io error: {err}"),
+            HandshakeError::ConnectionClosed => write!(f, "connection closed during handshake"),
+            HandshakeError::InvalidRequest => write!(f, "invalid opening handshake request"),
+            HandshakeError::MissingHeader(name) => write!(f, "missing {name} header"),
+            HandshakeError::HeaderTooLarge => write!(
+                f,
+                "handshake request exceeded {MAX_HEADER_SIZE} bytes without a terminator"
+            ),
+        }
+    }
+}
+
+fn header_end(buf: &[u8]) -> Option<usize> {
+    buf.windows(4).position(|w| w == b"\r\n\r\n").map(|i| i + 4)
+}
+
+fn accept_key(client_key: &str) -> String {
+    let mut hasher = Sha1::new();
+    hasher.update(client_key.as_bytes());
+    hasher.update(ACCEPT_GUID.as_bytes());
+    base64::engine::general_purpose::STANDARD.encode(hasher.finalize())
+}
+
+/// Performs the WebSocket opening handshake (RFC 6455 section 4) over `stream`.
+///
+/// On success, writes the `101 Switching Protocols` response and returns any bytes read past
+/// the end of the HTTP request so the caller doesn't lose the start of the first frame.
+pub async fn handshake(stream: &mut TcpStream) -> Result<Vec<u8>, HandshakeError> {
+    let mut buf = Vec::new();
+    let end = loop {
+        if let Some(end) = header_end(&buf) {
+            break end;
+        }
+        if buf.len() >= MAX_HEADER_SIZE {
+            return Err(HandshakeError::HeaderTooLarge);
+        }
+        let mut chunk = vec![0; 512.min(MAX_HEADER_SIZE - buf.len())];
+        let n = stream.read(&mut chunk).await?;
+        if n == 0 {
+            return Err(HandshakeError::ConnectionClosed);
+        }
+        chunk.truncate(n);
+        buf.extend_from_slice(&chunk);
+    };
+    let leftover = buf[end..].to_vec();
+    let request = std::str::from_utf8(&buf[..end]).map_err(|_| HandshakeError::InvalidRequest)?;
+    let mut lines = request.split("\r\n");
+    let request_line = lines.next().ok_or(HandshakeError::InvalidRequest)?;
+    if !request_line.starts_with("GET ") {
+        return Err(HandshakeError::InvalidRequest);
+    }
+
+    let mut has_upgrade = false;
+    let mut has_connection = false;
+    let mut has_version_13 = false;
+    let mut client_key = None;
+    for line in lines {
+        if line.is_empty() {
+            continue;
+        }
+        let (name, value) = line.split_once(':').ok_or(HandshakeError::InvalidRequest)?;
+        let value = value.trim();
+        match name.to_ascii_lowercase().as_str() {
+            "upgrade" => has_upgrade = value.eq_ignore_ascii_case("websocket"),
+            "connection" => {
+                has_connection = value
+                    .split(',')
+                    .any(|token| token.trim().eq_ignore_ascii_case("upgrade"))
+            }
+            "sec-websocket-version" => has_version_13 = value == "13",
+            "sec-websocket-key" => client_key = Some(value.to_string()),
+            _ => {}
+        }
+    }
+    if !has_upgrade || !has_connection || !has_version_13 {
+        return Err(HandshakeError::InvalidRequest);
+    }
+    let client_key = client_key.ok_or(HandshakeError::MissingHeader("Sec-WebSocket-Key"))?;
+
+    let response = format!(
+        "HTTP/1.1 101 Switching Protocols\r\n\
+         Upgrade: websocket\r\n\
+         Connection: Upgrade\r\n\
+         Sec-WebSocket-Accept: {}\r\n\r\n",
+        accept_key(&client_key)
+    );
+    stream.write_all(response.as_bytes()).await?;
+    Ok(leftover)
+}
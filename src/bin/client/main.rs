@@ -0,0 +1,46 @@
+use async_std::net::TcpListener;
+use futures::stream::StreamExt;
+
+mod assembler;
+mod config;
+mod frame;
+mod handshake;
+mod message;
+mod stream;
+
+use config::WebSocketConfig;
+use handshake::handshake;
+use stream::WebSocketStream;
+
+fn main() {
+    let runtime = tokio::runtime::Runtime::new().unwrap();
+    let config = WebSocketConfig::builder()
+        .max_frame_size(64 * 1024)
+        .max_message_size(1024 * 1024)
+        .build();
+    runtime.block_on(async {
+        let port = TcpListener::bind("localhost:3000").await.unwrap();
+        port.incoming()
+            .map(Result::unwrap)
+            .for_each_concurrent(None, |mut conn| async move {
+                let leftover = match handshake(&mut conn).await {
+                    Ok(leftover) => leftover,
+                    Err(e) => {
+                        println!("handshake failed: {e}");
+                        return;
+                    }
+                };
+                let mut messages = WebSocketStream::new(conn, config, leftover);
+                while let Some(message) = messages.next().await {
+                    match message {
+                        Ok(message) => println!("{message:?}"),
+                        Err(e) => {
+                            println!("{e}");
+                            break;
+                        }
+                    }
+                }
+            })
+            .await;
+    });
+}
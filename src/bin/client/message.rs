@@ -0,0 +1,169 @@
+use crate::frame::{Frame, OpCode};
+
+/// A WebSocket close status code (RFC 6455 section 7.4).
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum CloseCode {
+    Normal,
+    GoingAway,
+    Protocol,
+    Unsupported,
+    InvalidPayload,
+    Policy,
+    TooBig,
+    Internal,
+    /// 3000-4999: reserved for use by applications and libraries.
+    Application(u16),
+}
+
+impl CloseCode {
+    fn from_u16(code: u16) -> Result<Self, DecodeError> {
+        match code {
+            1000 => Ok(CloseCode::Normal),
+            1001 => Ok(CloseCode::GoingAway),
+            1002 => Ok(CloseCode::Protocol),
+            1003 => Ok(CloseCode::Unsupported),
+            1007 => Ok(CloseCode::InvalidPayload),
+            1008 => Ok(CloseCode::Policy),
+            1009 => Ok(CloseCode::TooBig),
+            1011 => Ok(CloseCode::Internal),
+            3000..=4999 => Ok(CloseCode::Application(code)),
+            _ => Err(DecodeError::InvalidCloseCode),
+        }
+    }
+
+    fn as_u16(&self) -> u16 {
+        match self {
+            CloseCode::Normal => 1000,
+            CloseCode::GoingAway => 1001,
+            CloseCode::Protocol => 1002,
+            CloseCode::Unsupported => 1003,
+            CloseCode::InvalidPayload => 1007,
+            CloseCode::Policy => 1008,
+            CloseCode::TooBig => 1009,
+            CloseCode::Internal => 1011,
+            CloseCode::Application(code) => *code,
+        }
+    }
+}
+
+/// A close code and optional human-readable reason sent in a Close frame's payload.
+#[derive(Debug, Clone)]
+pub struct CloseReason {
+    pub code: CloseCode,
+    pub description: Option<String>,
+}
+
+impl CloseReason {
+    fn encode(&self) -> Vec<u8> {
+        let mut out = self.code.as_u16().to_be_bytes().to_vec();
+        if let Some(description) = &self.description {
+            out.extend_from_slice(description.as_bytes());
+        }
+        out
+    }
+
+    /// Decodes a Close frame payload: empty means no reason given, otherwise the first two
+    /// bytes are a big-endian status code followed by a UTF-8 description. Reserved/invalid
+    /// codes (1004-1006, 0-999, anything outside the known and 3000-4999 ranges) are rejected.
+    pub(crate) fn decode(payload: &[u8]) -> Result<Option<Self>, DecodeError> {
+        if payload.is_empty() {
+            return Ok(None);
+        }
+        if payload.len() < 2 {
+            return Err(DecodeError::Malformed);
+        }
+        let code = CloseCode::from_u16(u16::from_be_bytes([payload[0], payload[1]]))?;
+        let description = if payload.len() > 2 {
+            Some(String::from_utf8(payload[2..].to_vec()).map_err(|_| DecodeError::Malformed)?)
+        } else {
+            None
+        };
+        Ok(Some(CloseReason { code, description }))
+    }
+}
+
+#[derive(Debug)]
+pub enum DecodeError {
+    Malformed,
+    InvalidCloseCode,
+}
+
+/// A higher-level WebSocket message, as handed to and received from callers.
+#[derive(Debug)]
+pub enum Message {
+    Text(String),
+    Binary(Vec<u8>),
+    Ping(Vec<u8>),
+    Pong(Vec<u8>),
+    Close(Option<CloseReason>),
+}
+
+impl Message {
+    /// Converts this message into the (unmasked, unfragmented) frame(s) that represent it.
+    pub fn into_frames(self) -> Vec<Frame> {
+        let (op_code, payload) = match self {
+            Message::Text(text) => (OpCode::Text, text.into_bytes()),
+            Message::Binary(bytes) => (OpCode::Binary, bytes),
+            Message::Ping(bytes) => (OpCode::Ping, bytes),
+            Message::Pong(bytes) => (OpCode::Pong, bytes),
+            Message::Close(reason) => (
+                OpCode::Close,
+                reason.map(|reason| reason.encode()).unwrap_or_default(),
+            ),
+        };
+        vec![Frame {
+            is_last_frag: true,
+            op_code,
+            payload,
+        }]
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn from_u16_accepts_known_codes() {
+        assert_eq!(CloseCode::from_u16(1000).unwrap(), CloseCode::Normal);
+        assert_eq!(CloseCode::from_u16(1001).unwrap(), CloseCode::GoingAway);
+        assert_eq!(CloseCode::from_u16(1002).unwrap(), CloseCode::Protocol);
+        assert_eq!(CloseCode::from_u16(1003).unwrap(), CloseCode::Unsupported);
+        assert_eq!(CloseCode::from_u16(1007).unwrap(), CloseCode::InvalidPayload);
+        assert_eq!(CloseCode::from_u16(1008).unwrap(), CloseCode::Policy);
+        assert_eq!(CloseCode::from_u16(1009).unwrap(), CloseCode::TooBig);
+        assert_eq!(CloseCode::from_u16(1011).unwrap(), CloseCode::Internal);
+    }
+
+    #[test]
+    fn from_u16_accepts_the_application_range() {
+        assert_eq!(CloseCode::from_u16(3000).unwrap(), CloseCode::Application(3000));
+        assert_eq!(CloseCode::from_u16(4999).unwrap(), CloseCode::Application(4999));
+    }
+
+    #[test]
+    fn from_u16_rejects_reserved_and_out_of_range_codes() {
+        for code in [0, 999, 1004, 1005, 1006, 2999, 5000] {
+            assert!(
+                matches!(CloseCode::from_u16(code), Err(DecodeError::InvalidCloseCode)),
+                "code {code} should be rejected"
+            );
+        }
+    }
+
+    #[test]
+    fn decode_round_trips_through_encode() {
+        let reason = CloseReason {
+            code: CloseCode::GoingAway,
+            description: Some("bye".to_string()),
+        };
+        let decoded = CloseReason::decode(&reason.encode()).unwrap().unwrap();
+        assert_eq!(decoded.code, reason.code);
+        assert_eq!(decoded.description, reason.description);
+    }
+
+    #[test]
+    fn decode_empty_payload_is_no_reason() {
+        assert!(CloseReason::decode(&[]).unwrap().is_none());
+    }
+}
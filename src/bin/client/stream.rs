@@ -0,0 +1,378 @@
+use std::pin::Pin;
+use std::task::{Context, Poll};
+
+use bytes::{Buf, BytesMut};
+use futures::io::{AsyncRead, AsyncWrite};
+use futures::ready;
+use futures::stream::Stream;
+
+use crate::assembler::{AssembleError, MessageAssembler};
+use crate::config::WebSocketConfig;
+use crate::frame::{parse_frame, ParseError};
+use crate::message::Message;
+
+const READ_CHUNK_SIZE: usize = 4096;
+
+#[derive(Debug)]
+pub enum ProtocolError {
+    Parse(ParseError),
+    Assemble(AssembleError),
+    Io(std::io::Error),
+}
+
+impl From<ParseError> for ProtocolError {
+    fn from(err: ParseError) -> Self {
+        ProtocolError::Parse(err)
+    }
+}
+
+impl From<AssembleError> for ProtocolError {
+    fn from(err: AssembleError) -> Self {
+        ProtocolError::Assemble(err)
+    }
+}
+
+impl From<std::io::Error> for ProtocolError {
+    fn from(err: std::io::Error) -> Self {
+        ProtocolError::Io(err)
+    }
+}
+
+impl std::fmt::Display for ProtocolError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            ProtocolError::Parse(err) => write!(f, "frame parse error: {err:?}"),
+            ProtocolError::Assemble(err) => write!(f, "message assembly error: {err:?}"),
+            ProtocolError::Io(err) => write!(f, "io error: {err}"),
+        }
+    }
+}
+
+/// An outbound control-frame reply that may not fit in one `poll_write` call. Kept as a cursor
+/// on `WebSocketStream` so a `Pending` write is resumed on the next `poll_next` instead of the
+/// reply silently being dropped on the floor.
+struct PendingWrite {
+    buf: Vec<u8>,
+    written: usize,
+}
+
+/// A server-side WebSocket connection exposed as a `Stream` of complete `Message`s.
+///
+/// Reads are driven from `poll_next`: each call grows an internal buffer only as needed and
+/// advances past consumed frames with `BytesMut::advance` rather than reallocating the buffer.
+/// Ping frames are answered with Pong automatically; the stream ends after the peer's Close.
+pub struct WebSocketStream<R> {
+    reader: R,
+    config: WebSocketConfig,
+    assembler: MessageAssembler,
+    buffer: BytesMut,
+    pending_write: Option<PendingWrite>,
+    /// The Close message to yield once our echoed Close frame has finished writing.
+    pending_close: Option<Message>,
+    done: bool,
+}
+
+impl<R> WebSocketStream<R> {
+    /// Wraps `reader` as a message stream. `leftover` is any bytes already read past the
+    /// handshake's HTTP request that belong to the first frame.
+    pub fn new(reader: R, config: WebSocketConfig, leftover: Vec<u8>) -> Self {
+        let mut buffer = BytesMut::with_capacity(leftover.len().max(READ_CHUNK_SIZE));
+        buffer.extend_from_slice(&leftover);
+        WebSocketStream {
+            reader,
+            assembler: MessageAssembler::new(config.max_message_size),
+            config,
+            buffer,
+            pending_write: None,
+            pending_close: None,
+            done: false,
+        }
+    }
+}
+
+impl<R: AsyncRead + AsyncWrite + Unpin> WebSocketStream<R> {
+    /// Queues `message` to be written out, encoded unmasked (server-to-client frames are never
+    /// masked). Only one reply is ever in flight at a time.
+    fn queue_write(&mut self, message: Message) {
+        let mut buf = Vec::new();
+        for frame in message.into_frames() {
+            buf.extend_from_slice(&frame.encode(None));
+        }
+        self.pending_write = Some(PendingWrite { buf, written: 0 });
+    }
+
+    /// Resumes the queued write, if any, picking up from wherever the last `Pending` left off.
+    fn poll_flush_pending(&mut self, cx: &mut Context<'_>) -> Poll<std::io::Result<()>> {
+        let Some(pending) = self.pending_write.as_mut() else {
+            return Poll::Ready(Ok(()));
+        };
+        while pending.written < pending.buf.len() {
+            let n = ready!(
+                Pin::new(&mut self.reader).poll_write(cx, &pending.buf[pending.written..])
+            )?;
+            if n == 0 {
+                return Poll::Ready(Err(std::io::Error::new(
+                    std::io::ErrorKind::WriteZero,
+                    "failed to write whole buffer",
+                )));
+            }
+            pending.written += n;
+        }
+        self.pending_write = None;
+        Poll::Ready(Ok(()))
+    }
+}
+
+impl<R: AsyncRead + AsyncWrite + Unpin> Stream for WebSocketStream<R> {
+    type Item = Result<Message, ProtocolError>;
+
+    fn poll_next(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Option<Self::Item>> {
+        let this = self.get_mut();
+        if this.done {
+            return Poll::Ready(None);
+        }
+        // A reply queued by a previous poll (e.g. a Pong, or the Close echo, that hit `Pending`)
+        // must finish before we consume any more input, so it's never silently dropped.
+        if let Err(err) = ready!(this.poll_flush_pending(cx)) {
+            this.done = true;
+            return Poll::Ready(Some(Err(err.into())));
+        }
+        if let Some(message) = this.pending_close.take() {
+            this.done = true;
+            return Poll::Ready(Some(Ok(message)));
+        }
+        loop {
+            match parse_frame(&this.buffer, true, this.config.max_frame_size) {
+                Ok((n, frame)) => {
+                    this.buffer.advance(n);
+                    match this.assembler.process(frame) {
+                        Ok(Some(Message::Ping(payload))) => {
+                            this.queue_write(Message::Pong(payload));
+                            if let Err(err) = ready!(this.poll_flush_pending(cx)) {
+                                this.done = true;
+                                return Poll::Ready(Some(Err(err.into())));
+                            }
+                        }
+                        Ok(Some(Message::Close(reason))) => {
+                            // Honor the closing handshake by echoing the peer's close code back.
+                            this.queue_write(Message::Close(reason.clone()));
+                            let message = Message::Close(reason);
+                            match this.poll_flush_pending(cx) {
+                                Poll::Ready(Ok(())) => {
+                                    this.done = true;
+                                    return Poll::Ready(Some(Ok(message)));
+                                }
+                                Poll::Ready(Err(err)) => {
+                                    this.done = true;
+                                    return Poll::Ready(Some(Err(err.into())));
+                                }
+                                Poll::Pending => {
+                                    this.pending_close = Some(message);
+                                    return Poll::Pending;
+                                }
+                            }
+                        }
+                        Ok(Some(message)) => return Poll::Ready(Some(Ok(message))),
+                        Ok(None) => {}
+                        Err(err) => {
+                            this.done = true;
+                            return Poll::Ready(Some(Err(err.into())));
+                        }
+                    }
+                }
+                Err(ParseError::Unfinished) => {
+                    let mut chunk = [0; READ_CHUNK_SIZE];
+                    let n = match ready!(Pin::new(&mut this.reader).poll_read(cx, &mut chunk)) {
+                        Ok(n) => n,
+                        Err(err) => {
+                            this.done = true;
+                            return Poll::Ready(Some(Err(err.into())));
+                        }
+                    };
+                    if n == 0 {
+                        this.done = true;
+                        return Poll::Ready(None);
+                    }
+                    this.buffer.extend_from_slice(&chunk[..n]);
+                }
+                Err(err) => {
+                    this.done = true;
+                    return Poll::Ready(Some(Err(err.into())));
+                }
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::cell::RefCell;
+    use std::collections::VecDeque;
+    use std::rc::Rc;
+
+    use futures::task::noop_waker_ref;
+
+    use crate::frame::OpCode;
+    use crate::message::CloseCode;
+
+    use super::*;
+
+    #[derive(Default)]
+    struct MockIoState {
+        written: Vec<u8>,
+        write_blocks_remaining: usize,
+    }
+
+    /// A fake connection whose reads come from a queue of preset chunks and whose writes can be
+    /// made to return `Pending` a fixed number of times before succeeding, so the Ping/Close
+    /// reply paths can be driven through backpressure without a real executor.
+    struct MockIo {
+        read_chunks: VecDeque<Vec<u8>>,
+        state: Rc<RefCell<MockIoState>>,
+    }
+
+    impl AsyncRead for MockIo {
+        fn poll_read(
+            self: Pin<&mut Self>,
+            _cx: &mut Context<'_>,
+            buf: &mut [u8],
+        ) -> Poll<std::io::Result<usize>> {
+            let this = self.get_mut();
+            match this.read_chunks.pop_front() {
+                Some(chunk) => {
+                    buf[..chunk.len()].copy_from_slice(&chunk);
+                    Poll::Ready(Ok(chunk.len()))
+                }
+                None => Poll::Ready(Ok(0)),
+            }
+        }
+    }
+
+    impl AsyncWrite for MockIo {
+        fn poll_write(
+            self: Pin<&mut Self>,
+            _cx: &mut Context<'_>,
+            buf: &[u8],
+        ) -> Poll<std::io::Result<usize>> {
+            let mut state = self.state.borrow_mut();
+            if state.write_blocks_remaining > 0 {
+                state.write_blocks_remaining -= 1;
+                return Poll::Pending;
+            }
+            state.written.extend_from_slice(buf);
+            Poll::Ready(Ok(buf.len()))
+        }
+
+        fn poll_flush(
+            self: Pin<&mut Self>,
+            _cx: &mut Context<'_>,
+        ) -> Poll<std::io::Result<()>> {
+            Poll::Ready(Ok(()))
+        }
+
+        fn poll_close(
+            self: Pin<&mut Self>,
+            _cx: &mut Context<'_>,
+        ) -> Poll<std::io::Result<()>> {
+            Poll::Ready(Ok(()))
+        }
+    }
+
+    fn poll_once<R: AsyncRead + AsyncWrite + Unpin>(
+        stream: &mut WebSocketStream<R>,
+    ) -> Poll<Option<Result<Message, ProtocolError>>> {
+        let waker = noop_waker_ref();
+        let mut cx = Context::from_waker(waker);
+        Pin::new(stream).poll_next(&mut cx)
+    }
+
+    fn client_frame(op_code: OpCode, payload: &[u8]) -> Vec<u8> {
+        crate::frame::Frame {
+            is_last_frag: true,
+            op_code,
+            payload: payload.to_vec(),
+        }
+        .encode(Some(0x01020304))
+    }
+
+    fn stream_with(
+        read_chunks: Vec<Vec<u8>>,
+        write_blocks_remaining: usize,
+    ) -> (WebSocketStream<MockIo>, Rc<RefCell<MockIoState>>) {
+        let state = Rc::new(RefCell::new(MockIoState {
+            write_blocks_remaining,
+            ..Default::default()
+        }));
+        let io = MockIo {
+            read_chunks: read_chunks.into(),
+            state: state.clone(),
+        };
+        (
+            WebSocketStream::new(io, WebSocketConfig::builder().build(), Vec::new()),
+            state,
+        )
+    }
+
+    #[test]
+    fn ping_reply_survives_a_pending_write_and_is_retried() {
+        let (mut stream, state) = stream_with(vec![client_frame(OpCode::Ping, b"ping")], 1);
+
+        // The write backs off with Pending; the Pong must not be dropped on the floor.
+        assert!(matches!(poll_once(&mut stream), Poll::Pending));
+        assert!(state.borrow().written.is_empty());
+
+        // The retried write completes, and the stream then sees EOF and ends.
+        assert!(matches!(poll_once(&mut stream), Poll::Ready(None)));
+        let expected = crate::frame::Frame {
+            is_last_frag: true,
+            op_code: OpCode::Pong,
+            payload: b"ping".to_vec(),
+        }
+        .encode(None);
+        assert_eq!(state.borrow().written, expected);
+    }
+
+    #[test]
+    fn close_is_echoed_back_and_then_yielded() {
+        let close_payload = [0x03, 0xE8, b'b', b'y', b'e'];
+        let (mut stream, state) = stream_with(vec![client_frame(OpCode::Close, &close_payload)], 1);
+
+        // The echo write backs off with Pending; the Close must be held, not lost or yielded
+        // ahead of its echo.
+        assert!(matches!(poll_once(&mut stream), Poll::Pending));
+        assert!(state.borrow().written.is_empty());
+
+        match poll_once(&mut stream) {
+            Poll::Ready(Some(Ok(Message::Close(Some(reason))))) => {
+                assert_eq!(reason.code, CloseCode::Normal);
+                assert_eq!(reason.description.as_deref(), Some("bye"));
+            }
+            other => panic!("expected an echoed Close message, got {other:?}"),
+        }
+        let expected = crate::frame::Frame {
+            is_last_frag: true,
+            op_code: OpCode::Close,
+            payload: close_payload.to_vec(),
+        }
+        .encode(None);
+        assert_eq!(state.borrow().written, expected);
+
+        // The stream is done after the Close; it never yields anything else.
+        assert!(matches!(poll_once(&mut stream), Poll::Ready(None)));
+    }
+
+    #[test]
+    fn a_frame_split_across_reads_is_assembled_before_parsing() {
+        let encoded = client_frame(OpCode::Binary, b"hello");
+        let (first, second) = encoded.split_at(2);
+        let (mut stream, _state) =
+            stream_with(vec![first.to_vec(), second.to_vec()], 0);
+
+        // Only half the frame has arrived: parsing can't finish yet, so poll_next reads again
+        // instead of yielding.
+        match poll_once(&mut stream) {
+            Poll::Ready(Some(Ok(Message::Binary(payload)))) => assert_eq!(payload, b"hello"),
+            other => panic!("expected an assembled Binary message, got {other:?}"),
+        }
+    }
+}